@@ -1,7 +1,7 @@
 use chrono::NaiveDate;
 use decimal::d128;
+use regex::{Captures, Regex};
 use serde::Deserialize;
-use std::collections::HashMap;
 use std::fmt;
 use std::ops::Neg;
 use std::path::PathBuf;
@@ -22,7 +22,7 @@ struct Opt {
 #[derive(Debug, Deserialize)]
 struct YamlConfig {
     csv: Config,
-    transactions: Option<HashMap<String, TransactionRule>>,
+    transactions: Option<Vec<TransactionRule>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -31,22 +31,95 @@ struct Config {
     processing_account: String,
     default_account: String,
     date_format: String,
-    date: i64,
-    amount_in: i64,
-    amount_out: i64,
-    description: i64,
+    date: ColumnRef,
+    /// Single signed amount column, negative for outflows. Mutually
+    /// exclusive with `amount_in`/`amount_out`.
+    amount: Option<ColumnRef>,
+    amount_in: Option<ColumnRef>,
+    amount_out: Option<ColumnRef>,
+    description: ColumnRef,
     /// The payee of the transaction. Will be omitted if empty.
-    payee: Option<i64>,
+    payee: Option<ColumnRef>,
     delimiter: Option<char>,
     skip: Option<i64>,
     toggle_sign: Option<bool>,
     quote: Option<char>,
+    /// Whether the CSV file has a header row. Required to be `true` to
+    /// resolve any `ColumnRef::Name` column reference.
+    has_headers: Option<bool>,
+    /// Column holding the account balance after the transaction, if the
+    /// bank reports one. When present, a `balance` directive is emitted
+    /// after each transaction so the generated ledger self-verifies against
+    /// the bank's running total.
+    balance: Option<ColumnRef>,
+    /// Per-row currency override for the processing account's leg, e.g. a
+    /// traded commodity or cryptocurrency. Requires `price` or `cost`.
+    commodity: Option<ColumnRef>,
+    /// Per-unit price of `commodity` in the base `currency`, rendered as
+    /// `@ <price> <currency>`.
+    price: Option<ColumnRef>,
+    /// Total cost of the row in the base `currency`, rendered as
+    /// `@@ <cost> <currency>`. Takes precedence over `price` when both
+    /// resolve a value for a row.
+    cost: Option<ColumnRef>,
+    /// Divides the parsed amount by this factor, e.g. `1000` for
+    /// milliunit formats such as YNAB's, or `100` for cents-as-integers
+    /// exports. Applied before `toggle_sign`.
+    scale: Option<i32>,
+}
+
+/// A CSV column, referenced either by its zero-based index or by its header
+/// name (which requires `has_headers: true`).
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ColumnRef {
+    Index(usize),
+    Name(String),
+}
+
+impl ColumnRef {
+    /// Resolves this column reference to a column index, looking up
+    /// `ColumnRef::Name` in `headers`.
+    fn resolve(&self, headers: Option<&csv::StringRecord>) -> Result<usize, String> {
+        match self {
+            ColumnRef::Index(index) => Ok(*index),
+            ColumnRef::Name(name) => {
+                let headers = headers.ok_or_else(|| {
+                    format!(
+                        "column '{}' is referenced by name but `has_headers` is not set to true",
+                        name
+                    )
+                })?;
+                headers
+                    .iter()
+                    .position(|header| header == name)
+                    .ok_or_else(|| format!("no column named '{}' in the CSV header row", name))
+            }
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
 struct TransactionRule {
+    /// Pattern matched against the description (or the payee, if
+    /// `match_payee` is set) to decide whether this rule applies. Rules are
+    /// tried in YAML order and the first match wins. A missing pattern
+    /// matches any transaction, which is handy as a catch-all at the end of
+    /// the list.
+    #[serde(rename = "match")]
+    pattern: Option<String>,
+    /// Treat `pattern` as a literal substring rather than a regular
+    /// expression.
+    contains: Option<bool>,
+    /// Match `pattern` against the payee instead of the description.
+    match_payee: Option<bool>,
     account: Option<String>,
     info: Option<String>,
+    /// Extra legs to split the transaction across, e.g. a fee posting or a
+    /// category split. When absent, the transaction is posted as the usual
+    /// two legs: the processing account and `account` (or
+    /// `default_account`).
+    postings: Option<Vec<Posting>>,
 }
 
 impl TransactionRule {
@@ -57,26 +130,344 @@ impl TransactionRule {
     fn account(&self) -> Option<&str> {
         self.account.as_ref().map(|s| s.as_str())
     }
+
+    /// Compiles this rule's `match` pattern into a `Regex`. A missing
+    /// pattern compiles to a regex that matches anything.
+    fn compile(&self) -> Result<Regex, regex::Error> {
+        match &self.pattern {
+            Some(pattern) if self.contains == Some(true) => Regex::new(&regex::escape(pattern)),
+            Some(pattern) => Regex::new(pattern),
+            None => Ok(Regex::new("").expect("empty pattern is always valid")),
+        }
+    }
+}
+
+/// One leg of a multi-posting rule. Exactly one of `amount`, `percentage`
+/// or `remainder` should be set: `amount` posts a fixed absolute amount,
+/// `percentage` posts that percentage of the transaction's magnitude, and
+/// `remainder` posts whatever is needed to balance the other legs.
+#[derive(Debug, Deserialize)]
+struct Posting {
+    account: String,
+    amount: Option<d128>,
+    percentage: Option<d128>,
+    #[serde(default)]
+    remainder: bool,
+}
+
+/// Builds the list of (account, signed amount) legs for a transaction,
+/// given the rule's extra `postings` (if any). The first leg is always the
+/// processing account, posted for `leg_magnitude`; `default_account` is
+/// used when the rule specifies no postings at all. Percentages,
+/// remainders, and the zero-sum check are all computed against
+/// `balance_magnitude`, the transaction's value expressed in the base
+/// currency, which equals `leg_magnitude` unless a per-row commodity
+/// conversion is in effect.
+fn build_postings(
+    postings: Option<&[Posting]>,
+    processing_account: &str,
+    default_account: &str,
+    leg_magnitude: d128,
+    balance_magnitude: d128,
+) -> Result<Vec<(String, d128)>, String> {
+    let postings = match postings {
+        None => {
+            return Ok(vec![
+                (processing_account.to_string(), leg_magnitude),
+                (default_account.to_string(), balance_magnitude.neg()),
+            ])
+        }
+        Some(postings) => postings,
+    };
+
+    let mut amounts: Vec<Option<d128>> = vec![None; postings.len()];
+    let mut remainder_index = None;
+    let mut fixed_sum = d128::from(0);
+    for (i, posting) in postings.iter().enumerate() {
+        let amount = if let Some(amount) = posting.amount {
+            amount
+        } else if let Some(percentage) = posting.percentage {
+            balance_magnitude.neg() * percentage / d128::from(100)
+        } else if posting.remainder {
+            if remainder_index.is_some() {
+                return Err("a transaction rule may only have one `remainder` posting".into());
+            }
+            remainder_index = Some(i);
+            continue;
+        } else {
+            return Err(format!(
+                "posting for '{}' needs one of `amount`, `percentage`, or `remainder`",
+                posting.account
+            ));
+        };
+        fixed_sum += amount;
+        amounts[i] = Some(amount);
+    }
+    if let Some(i) = remainder_index {
+        amounts[i] = Some(balance_magnitude.neg() - fixed_sum);
+    }
+
+    let mut legs = vec![(processing_account.to_string(), leg_magnitude)];
+    for (posting, amount) in postings.iter().zip(amounts) {
+        legs.push((
+            posting.account.clone(),
+            amount.expect("every posting amount is resolved above"),
+        ));
+    }
+
+    let total = balance_magnitude
+        + legs[1..]
+            .iter()
+            .fold(d128::from(0), |acc, (_, amount)| acc + *amount);
+    if total != d128::from(0) {
+        return Err(format!("postings do not sum to zero (off by {})", total));
+    }
+
+    Ok(legs)
+}
+
+#[cfg(test)]
+mod build_postings_tests {
+    use super::*;
+
+    fn posting(
+        account: &str,
+        amount: Option<d128>,
+        percentage: Option<d128>,
+        remainder: bool,
+    ) -> Posting {
+        Posting {
+            account: account.to_string(),
+            amount,
+            percentage,
+            remainder,
+        }
+    }
+
+    #[test]
+    fn default_two_leg_split() {
+        let legs = build_postings(
+            None,
+            "Assets:Checking",
+            "Expenses:Groceries",
+            "-100.00".parse().unwrap(),
+            "-100.00".parse().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            legs,
+            vec![
+                ("Assets:Checking".to_string(), "-100.00".parse().unwrap()),
+                ("Expenses:Groceries".to_string(), "100.00".parse().unwrap()),
+            ]
+        );
+    }
+
+    #[test]
+    fn fixed_amount_plus_remainder() {
+        let postings = vec![
+            posting("Expenses:Fees", Some("5.00".parse().unwrap()), None, false),
+            posting("Expenses:Groceries", None, None, true),
+        ];
+        let legs = build_postings(
+            Some(&postings),
+            "Assets:Checking",
+            "Expenses:Default",
+            "-105.00".parse().unwrap(),
+            "-105.00".parse().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            legs,
+            vec![
+                ("Assets:Checking".to_string(), "-105.00".parse().unwrap()),
+                ("Expenses:Fees".to_string(), "5.00".parse().unwrap()),
+                ("Expenses:Groceries".to_string(), "100.00".parse().unwrap()),
+            ]
+        );
+    }
+
+    #[test]
+    fn percentage_only_split() {
+        let postings = vec![
+            posting("Expenses:Food", None, Some("50".parse().unwrap()), false),
+            posting(
+                "Expenses:Household",
+                None,
+                Some("50".parse().unwrap()),
+                false,
+            ),
+        ];
+        let legs = build_postings(
+            Some(&postings),
+            "Assets:Checking",
+            "Expenses:Default",
+            "-100.00".parse().unwrap(),
+            "-100.00".parse().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            legs,
+            vec![
+                ("Assets:Checking".to_string(), "-100.00".parse().unwrap()),
+                ("Expenses:Food".to_string(), "50.00".parse().unwrap()),
+                ("Expenses:Household".to_string(), "50.00".parse().unwrap()),
+            ]
+        );
+    }
+
+    #[test]
+    fn percentage_plus_remainder() {
+        let postings = vec![
+            posting("Expenses:Food", None, Some("50".parse().unwrap()), false),
+            posting("Expenses:Household", None, None, true),
+        ];
+        let legs = build_postings(
+            Some(&postings),
+            "Assets:Checking",
+            "Expenses:Default",
+            "-100.00".parse().unwrap(),
+            "-100.00".parse().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            legs,
+            vec![
+                ("Assets:Checking".to_string(), "-100.00".parse().unwrap()),
+                ("Expenses:Food".to_string(), "50.00".parse().unwrap()),
+                ("Expenses:Household".to_string(), "50.00".parse().unwrap()),
+            ]
+        );
+    }
+}
+
+/// How to read the signed transaction amount from a CSV row: either one
+/// column that is already signed, or a pair of separate in/out columns.
+#[derive(Debug)]
+enum AmountSource {
+    Signed(usize),
+    InOut(usize, usize),
+}
+
+/// How the processing account's leg prices a per-row `commodity` in the
+/// base currency: a per-unit rate (`@`) or a total cost (`@@`).
+#[derive(Debug)]
+enum PriceAnnotation {
+    PerUnit(d128),
+    Total(d128),
+}
+
+/// Expands `$0`, `$1`, ... (or the braced form `${0}`, `${1}`, ...) in
+/// `template` with the corresponding capture group from `captures`. Groups
+/// that didn't participate in the match, that don't exist, or whose index
+/// doesn't fit in a `usize`, expand to an empty string.
+fn expand_captures(template: &str, captures: &Captures) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            result.push(c);
+            continue;
+        }
+
+        let braced = chars.peek() == Some(&'{');
+        if braced {
+            chars.next();
+        }
+
+        let mut digits = String::new();
+        while let Some(d) = chars.peek() {
+            if d.is_ascii_digit() {
+                digits.push(*d);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        if braced && chars.peek() != Some(&'}') {
+            // Not a well-formed `${N}`: emit what we consumed literally.
+            result.push('$');
+            result.push('{');
+            result.push_str(&digits);
+            continue;
+        }
+        if braced {
+            chars.next(); // consume '}'
+        }
+
+        if digits.is_empty() {
+            result.push('$');
+            if braced {
+                result.push('{');
+                result.push('}');
+            }
+        } else if let Some(m) = digits.parse().ok().and_then(|i: usize| captures.get(i)) {
+            result.push_str(m.as_str());
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod expand_captures_tests {
+    use super::*;
+
+    #[test]
+    fn bare_dollar_groups() {
+        let re = Regex::new(r"Transfer to (\w+)").unwrap();
+        let captures = re.captures("Transfer to Bob").unwrap();
+        assert_eq!(expand_captures("$0: $1", &captures), "Transfer to Bob: Bob");
+    }
+
+    #[test]
+    fn braced_dollar_group() {
+        let re = Regex::new(r"Transfer to (\w+)").unwrap();
+        let captures = re.captures("Transfer to Bob").unwrap();
+        assert_eq!(expand_captures("Transfer to ${1}", &captures), "Transfer to Bob");
+    }
+
+    #[test]
+    fn unmatched_group_expands_to_empty() {
+        let re = Regex::new(r"(foo)|(bar)").unwrap();
+        let captures = re.captures("bar").unwrap();
+        // Group 1 ("foo") didn't participate in this match.
+        assert_eq!(expand_captures("[$1]", &captures), "[]");
+        // Group 5 doesn't exist at all.
+        assert_eq!(expand_captures("[$5]", &captures), "[]");
+    }
+
+    #[test]
+    fn oversized_group_index_does_not_panic() {
+        let re = Regex::new(r"(\w+)").unwrap();
+        let captures = re.captures("hello").unwrap();
+        assert_eq!(
+            expand_captures("[$99999999999999999999]", &captures),
+            "[]"
+        );
+    }
 }
 
 #[derive(Debug)]
 struct Transaction<'a> {
     date: NaiveDate,
-    processing_account: &'a str,
-    other_account: &'a str,
     currency: &'a str,
-    magnitude: d128,
     payee: Option<&'a str>,
-    description: &'a str,
+    description: String,
+    /// The legs of the transaction, as (account, signed amount) pairs.
+    /// Always has at least two entries and sums to zero.
+    postings: Vec<(String, d128)>,
+    /// When set, the first leg (the processing account) is denominated in
+    /// this commodity instead of `currency`, priced against `currency` via
+    /// the given `PriceAnnotation`.
+    commodity: Option<(String, PriceAnnotation)>,
 }
 
 impl<'a> std::fmt::Display for Transaction<'a> {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             fmt,
-            r#"{} * {} "{}"
-  {} {} {}
-  {} {} {}"#,
+            r#"{} * {} "{}""#,
             self.date,
             if let Some(payee) = self.payee {
                 format!(r#""{}""#, payee)
@@ -84,13 +475,25 @@ impl<'a> std::fmt::Display for Transaction<'a> {
                 "".into()
             },
             self.description,
-            self.processing_account,
-            self.magnitude,
-            self.currency,
-            self.other_account,
-            self.magnitude.neg(),
-            self.currency
-        )
+        )?;
+        for (i, (account, amount)) in self.postings.iter().enumerate() {
+            if i == 0 {
+                if let Some((commodity, price)) = &self.commodity {
+                    let (symbol, value) = match price {
+                        PriceAnnotation::PerUnit(price) => ("@", price),
+                        PriceAnnotation::Total(cost) => ("@@", cost),
+                    };
+                    write!(
+                        fmt,
+                        "\n  {} {} {} {} {} {}",
+                        account, amount, commodity, symbol, value, self.currency
+                    )?;
+                    continue;
+                }
+            }
+            write!(fmt, "\n  {} {} {}", account, amount, self.currency)?;
+        }
+        Ok(())
     }
 }
 
@@ -100,15 +503,72 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let yaml_file = std::fs::File::open(&opt.yaml_path)?;
     let root_config: YamlConfig = serde_yaml::from_reader(yaml_file)?;
     let config = root_config.csv;
-    let transaction_rules = root_config.transactions;
+    let transaction_rules = root_config
+        .transactions
+        .map(|rules| {
+            rules
+                .into_iter()
+                .map(|rule| {
+                    let regex = rule.compile()?;
+                    Ok((rule, regex))
+                })
+                .collect::<Result<Vec<_>, regex::Error>>()
+        })
+        .transpose()?;
     let csv_file = std::fs::File::open(opt.csv_path)?;
 
     let mut rdr = csv::ReaderBuilder::new()
         .delimiter(config.delimiter.map(|del| del as u8).unwrap_or(b','))
         .quote(config.quote.map(|del| del as u8).unwrap_or(b'\"'))
-        .has_headers(false)
+        .has_headers(config.has_headers.unwrap_or(false))
         .from_reader(csv_file);
 
+    let headers = if config.has_headers == Some(true) {
+        Some(rdr.headers()?.clone())
+    } else {
+        None
+    };
+    let date_col = config.date.resolve(headers.as_ref())?;
+    let amount_source = match (&config.amount, &config.amount_in, &config.amount_out) {
+        (Some(amount), None, None) => AmountSource::Signed(amount.resolve(headers.as_ref())?),
+        (None, Some(amount_in), Some(amount_out)) => AmountSource::InOut(
+            amount_in.resolve(headers.as_ref())?,
+            amount_out.resolve(headers.as_ref())?,
+        ),
+        _ => {
+            return Err(
+                "config.csv must set exactly one of `amount`, or both `amount_in` and `amount_out`"
+                    .into(),
+            )
+        }
+    };
+    let description_col = config.description.resolve(headers.as_ref())?;
+    let payee_col = config
+        .payee
+        .as_ref()
+        .map(|payee| payee.resolve(headers.as_ref()))
+        .transpose()?;
+    let balance_col = config
+        .balance
+        .as_ref()
+        .map(|balance| balance.resolve(headers.as_ref()))
+        .transpose()?;
+    let commodity_col = config
+        .commodity
+        .as_ref()
+        .map(|commodity| commodity.resolve(headers.as_ref()))
+        .transpose()?;
+    let price_col = config
+        .price
+        .as_ref()
+        .map(|price| price.resolve(headers.as_ref()))
+        .transpose()?;
+    let cost_col = config
+        .cost
+        .as_ref()
+        .map(|cost| cost.resolve(headers.as_ref()))
+        .transpose()?;
+
     let mut first = true;
     for result in rdr.records().skip(config.skip.unwrap_or(0) as usize) {
         let record = result?;
@@ -119,51 +579,138 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!();
         }
 
-        let payee = config
-            .payee
-            .map(|payee| &record[payee as usize])
+        let payee = payee_col
+            .map(|payee| &record[payee])
             .filter(|payee| !payee.is_empty());
-        let description = &record[config.description as usize];
-        let date = NaiveDate::parse_from_str(&record[config.date as usize], &config.date_format)?;
+        let description = &record[description_col];
+        let date = NaiveDate::parse_from_str(&record[date_col], &config.date_format)?;
 
-        // The current applicable rule, if any.
-        let current_transaction_rule = transaction_rules
-            .as_ref()
-            .and_then(|rules| rules.get(description));
+        // The first rule (in YAML order) whose pattern matches this
+        // transaction, along with its captures.
+        let matched_rule = transaction_rules.as_ref().and_then(|rules| {
+            rules.iter().find_map(|(rule, regex)| {
+                let haystack = if rule.match_payee == Some(true) {
+                    payee.unwrap_or("")
+                } else {
+                    description
+                };
+                regex.captures(haystack).map(|captures| (rule, captures))
+            })
+        });
 
-        let t = Transaction {
-            date,
-            description: current_transaction_rule
-                .and_then(TransactionRule::info)
-                .unwrap_or(description),
-            payee,
-            processing_account: &config.processing_account,
-            other_account: current_transaction_rule
-                .and_then(TransactionRule::account)
-                .unwrap_or(&config.default_account),
-            magnitude: {
-                let in_amount = &record[config.amount_in as usize];
-                let out_amount = &record[config.amount_out as usize];
-                let amt = if let Ok(amt) = in_amount.parse::<d128>() {
-                    amt
-                } else if let Ok(amt) = out_amount.parse::<d128>() {
-                    amt.neg()
+        let other_account = matched_rule
+            .as_ref()
+            .and_then(|(rule, captures)| {
+                rule.account()
+                    .map(|account| expand_captures(account, captures))
+            })
+            .unwrap_or_else(|| config.default_account.clone());
+        let magnitude = {
+            let mut amt = match amount_source {
+                AmountSource::Signed(amount_col) => record[amount_col]
+                    .parse::<d128>()
+                    .map_err(|_| format!("Could not parse amount for {}", description))?,
+                AmountSource::InOut(amount_in_col, amount_out_col) => {
+                    let in_amount = &record[amount_in_col];
+                    let out_amount = &record[amount_out_col];
+                    if let Ok(amt) = in_amount.parse::<d128>() {
+                        amt
+                    } else if let Ok(amt) = out_amount.parse::<d128>() {
+                        amt.neg()
+                    } else {
+                        Err(format!(
+                            "Could not parse either in or out amounts for {}",
+                            description
+                        ))?
+                    }
+                }
+            };
+            if let Some(scale) = config.scale {
+                amt /= d128::from(scale);
+            }
+            if config.toggle_sign == Some(true) {
+                amt = amt.neg();
+            }
+            amt
+        };
+        let commodity = commodity_col
+            .map(|commodity_col| -> Result<_, Box<dyn std::error::Error>> {
+                let commodity = record[commodity_col].to_string();
+                let cost = cost_col.map(|c| &record[c]).filter(|c| !c.is_empty());
+                let price = price_col.map(|c| &record[c]).filter(|c| !c.is_empty());
+                if let Some(cost) = cost {
+                    let cost = cost
+                        .parse::<d128>()
+                        .map_err(|_| format!("Could not parse cost for {}", description))?;
+                    Ok((commodity, PriceAnnotation::Total(cost)))
+                } else if let Some(price) = price {
+                    let price = price
+                        .parse::<d128>()
+                        .map_err(|_| format!("Could not parse price for {}", description))?;
+                    Ok((commodity, PriceAnnotation::PerUnit(price)))
                 } else {
                     Err(format!(
-                        "Could not parse either in or out amounts for {}",
+                        "'{}' has a commodity but neither `price` nor `cost` resolved a value",
                         description
-                    ))?
-                };
-                if config.toggle_sign == Some(true) {
-                    amt.neg()
-                } else {
-                    amt
+                    )
+                    .into())
                 }
-            },
+            })
+            .transpose()?;
+        let balance_magnitude = match &commodity {
+            Some((_, PriceAnnotation::Total(cost))) => *cost,
+            Some((_, PriceAnnotation::PerUnit(price))) => magnitude * *price,
+            None => magnitude,
+        };
+        let postings = build_postings(
+            matched_rule
+                .as_ref()
+                .and_then(|(rule, _)| rule.postings.as_deref()),
+            &config.processing_account,
+            &other_account,
+            magnitude,
+            balance_magnitude,
+        )?;
+
+        let t = Transaction {
+            date,
+            description: matched_rule
+                .as_ref()
+                .and_then(|(rule, captures)| {
+                    rule.info().map(|info| expand_captures(info, captures))
+                })
+                .unwrap_or_else(|| description.to_string()),
+            payee,
             currency: &config.currency,
+            postings,
+            commodity,
         };
 
-        println!("{}", t)
+        println!("{}", t);
+
+        if let Some(balance_col) = balance_col {
+            let balance = &record[balance_col];
+            if !balance.is_empty() {
+                let mut amt = balance
+                    .parse::<d128>()
+                    .map_err(|_| format!("Could not parse balance for {}", description))?;
+                if let Some(scale) = config.scale {
+                    amt /= d128::from(scale);
+                }
+                if config.toggle_sign == Some(true) {
+                    amt = amt.neg();
+                }
+                // beancount asserts the balance at the *start* of the given
+                // day, i.e. after all transactions dated the day before.
+                let balance_date = date
+                    .succ_opt()
+                    .ok_or("date overflow while computing balance assertion date")?;
+                println!(
+                    "\n{} balance {} {} {}",
+                    balance_date, config.processing_account, amt, config.currency
+                );
+            }
+        }
     }
 
     Ok(())